@@ -0,0 +1,224 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discovery::{self, BatteryPaths, CapacityUnit};
+use crate::error::Error;
+use crate::module::{Bar, RunPtr};
+use crate::output::ClickEvent;
+use crate::pulse::Pulse;
+use crate::util::{read_and_parse, read_and_trim};
+use crate::{Color, Config as MainConfig, ModuleMsg};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const PLACEHOLDER: &str = "-";
+const TICK_RATE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    tick: Option<u32>,
+    placeholder: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct InternalConfig {
+    tick: Duration,
+    batteries: Vec<BatteryPaths>,
+}
+
+impl From<&MainConfig> for InternalConfig {
+    fn from(config: &MainConfig) -> Self {
+        let mut tick = TICK_RATE;
+        if let Some(c) = &config.battery {
+            if let Some(t) = c.tick {
+                tick = Duration::from_millis(t as u64)
+            }
+        }
+        InternalConfig {
+            tick,
+            batteries: discovery::discover_batteries(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Battery<'a> {
+    placeholder: &'a str,
+}
+
+impl<'a> Battery<'a> {
+    pub fn with_config(config: &'a MainConfig) -> Self {
+        let mut placeholder = PLACEHOLDER;
+        if let Some(c) = &config.battery {
+            if let Some(p) = &c.placeholder {
+                placeholder = p
+            }
+        }
+        Battery { placeholder }
+    }
+}
+
+impl<'a> Bar for Battery<'a> {
+    fn name(&self) -> &str {
+        "battery"
+    }
+
+    fn run_fn(&self) -> RunPtr {
+        run
+    }
+
+    fn placeholder(&self) -> &str {
+        self.placeholder
+    }
+}
+
+pub fn run(
+    key: char,
+    main_config: MainConfig,
+    _: Arc<Mutex<Pulse>>,
+    tx: Sender<ModuleMsg>,
+    _: Receiver<ClickEvent>,
+) -> Result<(), Error> {
+    let config = InternalConfig::from(&main_config);
+    if config.batteries.is_empty() {
+        return Err("no battery found under /sys/class/power_supply".into());
+    }
+    loop {
+        let (text, color) = battery(&config.batteries)?;
+        tx.send(ModuleMsg(key, text, color))?;
+        thread::sleep(config.tick);
+    }
+}
+
+fn battery(batteries: &[BatteryPaths]) -> Result<(String, Option<Color>), Error> {
+    // Summing now/full-design only makes sense if every battery reports the
+    // same unit family; µWh and µAh aren't convertible without a voltage
+    // reading we don't have, so a mixed setup is rejected rather than
+    // silently producing a meaningless percentage.
+    if batteries.iter().any(|b| b.unit != batteries[0].unit) {
+        return Err("batteries report mixed energy_*/charge_* units, which can't be summed".into());
+    }
+    let mut full_design = 0u64;
+    let mut now = 0u64;
+    // A multi-battery laptop reports "Full"/"Charging" per battery; treat
+    // the bar as charging/full as soon as any one of them is.
+    let mut status = "Discharging".to_string();
+    for battery in batteries {
+        full_design += read_and_parse(&battery.full_design.to_string_lossy())? as u64;
+        now += read_and_parse(&battery.now.to_string_lossy())? as u64;
+        let battery_status = read_and_trim(&battery.status.to_string_lossy())?;
+        if battery_status == "Full" || battery_status == "Charging" {
+            status = battery_status;
+        }
+    }
+    let unit_label = match batteries[0].unit {
+        CapacityUnit::Energy => "energy",
+        CapacityUnit::Charge => "charge",
+    };
+    if full_design == 0 {
+        return Err(format!("battery reports a zero full-design {}", unit_label).into());
+    }
+    let battery_level = u32::try_from(100 * now / full_design)?;
+    let mut color = match battery_level {
+        0..=10 => Some(Color::Critical),
+        _ => None,
+    };
+    if status == "Full" {
+        color = Some(Color::Good)
+    }
+    Ok((
+        format!("{} {}%", get_battery_icon(&status, battery_level), battery_level),
+        color,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_battery(
+        dir: &std::path::Path,
+        idx: usize,
+        unit: CapacityUnit,
+        full_design: &str,
+        now: &str,
+        status: &str,
+    ) -> BatteryPaths {
+        let bat_dir = dir.join(format!("BAT{}", idx));
+        std::fs::create_dir_all(&bat_dir).unwrap();
+        let (now_name, full_name) = match unit {
+            CapacityUnit::Energy => ("energy_now", "energy_full_design"),
+            CapacityUnit::Charge => ("charge_now", "charge_full_design"),
+        };
+        std::fs::write(bat_dir.join(full_name), full_design).unwrap();
+        std::fs::write(bat_dir.join(now_name), now).unwrap();
+        std::fs::write(bat_dir.join("status"), status).unwrap();
+        BatteryPaths {
+            status: bat_dir.join("status"),
+            now: bat_dir.join(now_name),
+            full_design: bat_dir.join(full_name),
+            unit,
+        }
+    }
+
+    #[test]
+    fn sums_multiple_batteries_of_the_same_unit() {
+        let dir = std::env::temp_dir().join(format!("baru-test-battery-sum-{}", std::process::id()));
+        let b0 = write_battery(&dir, 0, CapacityUnit::Energy, "5000000", "2500000", "Discharging");
+        let b1 = write_battery(&dir, 1, CapacityUnit::Energy, "5000000", "2500000", "Discharging");
+        let (text, color) = battery(&[b0, b1]).unwrap();
+        assert_eq!(text, "󰁾 50%");
+        assert!(color.is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn rejects_mixed_energy_and_charge_units() {
+        let dir = std::env::temp_dir().join(format!("baru-test-battery-mixed-{}", std::process::id()));
+        let b0 = write_battery(&dir, 0, CapacityUnit::Energy, "5000000", "2500000", "Discharging");
+        let b1 = write_battery(&dir, 1, CapacityUnit::Charge, "5000000", "2500000", "Discharging");
+        let err = battery(&[b0, b1]).unwrap_err();
+        assert!(err.to_string().contains("mixed"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
+
+fn get_battery_icon(state: &str, level: u32) -> &'static str {
+    match state {
+        "Full" => "󰁹",
+        "Discharging" => match level {
+            0..=9 => "󰂎",
+            10..=19 => "󰁺",
+            20..=29 => "󰁻",
+            30..=39 => "󰁼",
+            40..=49 => "󰁽",
+            50..=59 => "󰁾",
+            60..=69 => "󰁿",
+            70..=79 => "󰂀",
+            80..=89 => "󰂁",
+            90..=99 => "󰂂",
+            100 => "󰁹",
+            _ => "󱃍",
+        },
+        "Charging" => match level {
+            0..=9 => "󰢟",
+            10..=19 => "󰢜",
+            20..=29 => "󰂆",
+            30..=39 => "󰂇",
+            40..=49 => "󰂈",
+            50..=59 => "󰢝",
+            60..=69 => "󰂉",
+            70..=79 => "󰢞",
+            80..=89 => "󰂊",
+            90..=99 => "󰂋",
+            100 => "󰂅",
+            _ => "󱃍",
+        },
+        _ => "󱃍",
+    }
+}
@@ -0,0 +1,98 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discovery;
+use crate::error::Error;
+use crate::module::{Bar, RunPtr};
+use crate::output::ClickEvent;
+use crate::pulse::Pulse;
+use crate::util::read_and_parse;
+use crate::{Config as MainConfig, ModuleMsg};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const PLACEHOLDER: &str = "-";
+const TICK_RATE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    tick: Option<u32>,
+    placeholder: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct InternalConfig {
+    tick: Duration,
+    backlight_path: PathBuf,
+}
+
+impl From<&MainConfig> for InternalConfig {
+    fn from(config: &MainConfig) -> Self {
+        let mut tick = TICK_RATE;
+        if let Some(c) = &config.brightness {
+            if let Some(t) = c.tick {
+                tick = Duration::from_millis(t as u64)
+            }
+        }
+        InternalConfig {
+            tick,
+            backlight_path: discovery::discover_backlight().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Brightness<'a> {
+    placeholder: &'a str,
+}
+
+impl<'a> Brightness<'a> {
+    pub fn with_config(config: &'a MainConfig) -> Self {
+        let mut placeholder = PLACEHOLDER;
+        if let Some(c) = &config.brightness {
+            if let Some(p) = &c.placeholder {
+                placeholder = p
+            }
+        }
+        Brightness { placeholder }
+    }
+}
+
+impl<'a> Bar for Brightness<'a> {
+    fn name(&self) -> &str {
+        "brightness"
+    }
+
+    fn run_fn(&self) -> RunPtr {
+        run
+    }
+
+    fn placeholder(&self) -> &str {
+        self.placeholder
+    }
+}
+
+pub fn run(
+    key: char,
+    main_config: MainConfig,
+    _: Arc<Mutex<Pulse>>,
+    tx: Sender<ModuleMsg>,
+    _: Receiver<ClickEvent>,
+) -> Result<(), Error> {
+    let config = InternalConfig::from(&main_config);
+    if config.backlight_path.as_os_str().is_empty() {
+        return Err("no backlight device found under /sys/class/backlight".into());
+    }
+    loop {
+        let brightness = read_and_parse(&config.backlight_path.join("actual_brightness").to_string_lossy())?;
+        let max_brightness = read_and_parse(&config.backlight_path.join("max_brightness").to_string_lossy())?;
+        let percentage = 100 * brightness / max_brightness;
+        tx.send(ModuleMsg(key, format!("󰃟 {}%", percentage), None))?;
+        thread::sleep(config.tick);
+    }
+}
@@ -0,0 +1,117 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::module::{Bar, RunPtr};
+use crate::output::ClickEvent;
+use crate::pulse::Pulse;
+use crate::{Config as MainConfig, ModuleMsg};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const PLACEHOLDER: &str = "-";
+const TICK_RATE: Duration = Duration::from_secs(1);
+const PROC_STAT: &str = "/proc/stat";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    tick: Option<u32>,
+    placeholder: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct InternalConfig {
+    tick: Duration,
+}
+
+impl From<&MainConfig> for InternalConfig {
+    fn from(config: &MainConfig) -> Self {
+        let mut tick = TICK_RATE;
+        if let Some(c) = &config.cpu {
+            if let Some(t) = c.tick {
+                tick = Duration::from_millis(t as u64)
+            }
+        }
+        InternalConfig { tick }
+    }
+}
+
+#[derive(Debug)]
+pub struct Cpu<'a> {
+    placeholder: &'a str,
+}
+
+impl<'a> Cpu<'a> {
+    pub fn with_config(config: &'a MainConfig) -> Self {
+        let mut placeholder = PLACEHOLDER;
+        if let Some(c) = &config.cpu {
+            if let Some(p) = &c.placeholder {
+                placeholder = p
+            }
+        }
+        Cpu { placeholder }
+    }
+}
+
+impl<'a> Bar for Cpu<'a> {
+    fn name(&self) -> &str {
+        "cpu"
+    }
+
+    fn run_fn(&self) -> RunPtr {
+        run
+    }
+
+    fn placeholder(&self) -> &str {
+        self.placeholder
+    }
+}
+
+pub fn run(
+    key: char,
+    main_config: MainConfig,
+    _: Arc<Mutex<Pulse>>,
+    tx: Sender<ModuleMsg>,
+    _: Receiver<ClickEvent>,
+) -> Result<(), Error> {
+    let config = InternalConfig::from(&main_config);
+    // Seed from one read before the loop so the first published value is a
+    // real delta instead of the average usage since boot.
+    let (mut prev_idle, mut prev_total) = read_times()?;
+    loop {
+        thread::sleep(config.tick);
+        let (idle, total) = read_times()?;
+        let diff_idle = idle - prev_idle;
+        let diff_total = total - prev_total;
+        let usage = if diff_total == 0 {
+            0
+        } else {
+            (1000 * (diff_total - diff_idle) / diff_total) / 10
+        };
+        prev_idle = idle;
+        prev_total = total;
+        tx.send(ModuleMsg(key, format!("{:3}%", usage), None))?;
+    }
+}
+
+fn read_times() -> Result<(i64, i64), Error> {
+    let proc_stat = File::open(PROC_STAT)?;
+    let mut reader = BufReader::new(proc_stat);
+    let mut buf = String::new();
+    reader.read_line(&mut buf)?;
+    let mut data = buf.split_whitespace();
+    data.next();
+    let times: Vec<i64> = data
+        .map(|n| n.parse::<i64>())
+        .collect::<Result<Vec<i64>, _>>()?;
+    let idle = times[3] + times[4];
+    let total = times.iter().sum();
+    Ok((idle, total))
+}
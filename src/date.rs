@@ -0,0 +1,94 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::module::{Bar, RunPtr};
+use crate::output::ClickEvent;
+use crate::pulse::Pulse;
+use crate::{Config as MainConfig, ModuleMsg};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const PLACEHOLDER: &str = "-";
+const TICK_RATE: Duration = Duration::from_secs(1);
+const FORMAT: &str = "%a. %-e %B %Y, %-kh%M";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    tick: Option<u32>,
+    placeholder: Option<String>,
+    format: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct InternalConfig<'a> {
+    tick: Duration,
+    format: &'a str,
+}
+
+impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
+    fn from(config: &'a MainConfig) -> Self {
+        let mut tick = TICK_RATE;
+        let mut format = FORMAT;
+        if let Some(c) = &config.date {
+            if let Some(t) = c.tick {
+                tick = Duration::from_millis(t as u64)
+            }
+            if let Some(v) = &c.format {
+                format = v;
+            }
+        }
+        InternalConfig { tick, format }
+    }
+}
+
+#[derive(Debug)]
+pub struct Date<'a> {
+    placeholder: &'a str,
+}
+
+impl<'a> Date<'a> {
+    pub fn with_config(config: &'a MainConfig) -> Self {
+        let mut placeholder = PLACEHOLDER;
+        if let Some(c) = &config.date {
+            if let Some(p) = &c.placeholder {
+                placeholder = p
+            }
+        }
+        Date { placeholder }
+    }
+}
+
+impl<'a> Bar for Date<'a> {
+    fn name(&self) -> &str {
+        "date"
+    }
+
+    fn run_fn(&self) -> RunPtr {
+        run
+    }
+
+    fn placeholder(&self) -> &str {
+        self.placeholder
+    }
+}
+
+pub fn run(
+    key: char,
+    main_config: MainConfig,
+    _: Arc<Mutex<Pulse>>,
+    tx: Sender<ModuleMsg>,
+    _: Receiver<ClickEvent>,
+) -> Result<(), Error> {
+    let config = InternalConfig::from(&main_config);
+    loop {
+        let now = Local::now();
+        tx.send(ModuleMsg(key, now.format(config.format).to_string(), None))?;
+        thread::sleep(config.tick);
+    }
+}
@@ -0,0 +1,193 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Resolves the sysfs paths modules need at startup instead of trusting
+//! hardcoded constants that only match the author's machine. The battery,
+//! hwmon and backlight lookups are called once and their result cached by
+//! the caller for the lifetime of the module; the wireless interface lookup
+//! is repeated on every tick/event.
+
+use crate::util::read_and_trim;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const POWER_SUPPLY_CLASS: &str = "/sys/class/power_supply";
+const HWMON_CLASS: &str = "/sys/class/hwmon";
+const BACKLIGHT_CLASS: &str = "/sys/class/backlight";
+const NET_CLASS: &str = "/sys/class/net";
+
+/// Either the `energy_*` or the `charge_*` sysfs attribute family, whichever
+/// the battery's driver exposes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CapacityUnit {
+    Energy,
+    Charge,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatteryPaths {
+    pub status: PathBuf,
+    pub now: PathBuf,
+    pub full_design: PathBuf,
+    pub unit: CapacityUnit,
+}
+
+/// Scans `/sys/class/power_supply/*` for every entry whose `type` is
+/// `Battery`. Laptops with more than one battery (e.g. a removable and a
+/// built-in one) report all of them; the caller sums their energy/charge.
+pub fn discover_batteries() -> Vec<BatteryPaths> {
+    let mut batteries = Vec::new();
+    let entries = match fs::read_dir(POWER_SUPPLY_CLASS) {
+        Ok(entries) => entries,
+        Err(_) => return batteries,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if read_and_trim(&path.join("type").to_string_lossy()).ok().as_deref() != Some("Battery") {
+            continue;
+        }
+        let unit = if path.join("energy_now").exists() {
+            CapacityUnit::Energy
+        } else if path.join("charge_now").exists() {
+            CapacityUnit::Charge
+        } else {
+            continue;
+        };
+        let (now, full_design) = match unit {
+            CapacityUnit::Energy => ("energy_now", "energy_full_design"),
+            CapacityUnit::Charge => ("charge_now", "charge_full_design"),
+        };
+        batteries.push(BatteryPaths {
+            status: path.join("status"),
+            now: path.join(now),
+            full_design: path.join(full_design),
+            unit,
+        });
+    }
+    batteries
+}
+
+#[derive(Debug, Clone)]
+pub struct HwmonSensor {
+    pub inputs: Vec<PathBuf>,
+}
+
+/// Scans `/sys/class/hwmon/*`, reads each `name` file and returns the
+/// `tempN_input` paths of the first device whose name is in `names`
+/// (e.g. `["coretemp", "k10temp"]`).
+pub fn discover_hwmon(names: &[&str]) -> Option<HwmonSensor> {
+    let entries = fs::read_dir(HWMON_CLASS).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = match read_and_trim(&path.join("name").to_string_lossy()) {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        if !names.contains(&name.as_str()) {
+            continue;
+        }
+        let inputs = temp_inputs(&path);
+        if !inputs.is_empty() {
+            return Some(HwmonSensor { inputs });
+        }
+    }
+    None
+}
+
+fn temp_inputs(hwmon_path: &Path) -> Vec<PathBuf> {
+    let mut inputs = Vec::new();
+    let entries = match fs::read_dir(hwmon_path) {
+        Ok(entries) => entries,
+        Err(_) => return inputs,
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name.starts_with("temp") && file_name.ends_with("_input") {
+            inputs.push(entry.path());
+        }
+    }
+    inputs.sort();
+    inputs
+}
+
+/// Scans `/sys/class/backlight/*` and returns the first device exposing
+/// both `actual_brightness` and `max_brightness`.
+pub fn discover_backlight() -> Option<PathBuf> {
+    let entries = fs::read_dir(BACKLIGHT_CLASS).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.join("actual_brightness").exists() && path.join("max_brightness").exists() {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Scans `/sys/class/net/*`, keeps only entries that expose a
+/// `wireless`/`phy80211` subdirectory (i.e. are actual wireless adapters)
+/// and returns the first one whose name matches `pattern`. Called on every
+/// tick/event so the wireless module keeps following the right interface
+/// even if it gets renamed or re-enumerated (`wlan0` becoming `wlp3s0`, a
+/// USB dongle appearing, ...).
+pub fn discover_wireless_interface(pattern: &Regex) -> Option<String> {
+    wireless_interface_in(Path::new(NET_CLASS), pattern)
+}
+
+/// The actual scan, parameterized over the `/sys/class/net` directory so
+/// tests can point it at a fixture instead of the real sysfs tree.
+fn wireless_interface_in(net_class: &Path, pattern: &Regex) -> Option<String> {
+    let mut entries: Vec<String> = fs::read_dir(net_class)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join("wireless").exists() || path.join("phy80211").exists())
+        .filter_map(|path| path.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .filter(|name| pattern.is_match(name))
+        .collect();
+    entries.sort();
+    entries.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_net_class(dir: &Path, wireless: &[&str], wired: &[&str]) {
+        for name in wireless {
+            fs::create_dir_all(dir.join(name).join("wireless")).unwrap();
+        }
+        for name in wired {
+            fs::create_dir_all(dir.join(name)).unwrap();
+        }
+    }
+
+    #[test]
+    fn matches_a_plain_interface_name() {
+        let dir = std::env::temp_dir().join(format!("baru-test-net-plain-{}", std::process::id()));
+        make_net_class(&dir, &["wlan0"], &["eth0"]);
+        let found = wireless_interface_in(&dir, &Regex::new("wlan0").unwrap());
+        assert_eq!(found, Some("wlan0".to_string()));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn ignores_non_wireless_interfaces_and_picks_the_first_match_by_name() {
+        let dir = std::env::temp_dir().join(format!("baru-test-net-filter-{}", std::process::id()));
+        make_net_class(&dir, &["wlp3s0", "wlan0"], &["eth0", "lo"]);
+        let found = wireless_interface_in(&dir, &Regex::new("^wl").unwrap());
+        assert_eq!(found, Some("wlan0".to_string()));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn returns_none_when_nothing_matches() {
+        let dir = std::env::temp_dir().join(format!("baru-test-net-none-{}", std::process::id()));
+        make_net_class(&dir, &["wlan0"], &["eth0"]);
+        let found = wireless_interface_in(&dir, &Regex::new("^eth").unwrap());
+        assert_eq!(found, None);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
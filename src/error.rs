@@ -0,0 +1,85 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::ModuleMsg;
+use libpulse_binding::error::PAErr;
+use std::fmt;
+use std::io;
+use std::num::{ParseIntError, TryFromIntError};
+use std::sync::mpsc::SendError;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse(ParseIntError),
+    TryFrom(TryFromIntError),
+    Send(SendError<ModuleMsg>),
+    Pulse(PAErr),
+    Regex(regex::Error),
+    Message(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::TryFrom(err) => write!(f, "{}", err),
+            Error::Send(err) => write!(f, "{}", err),
+            Error::Pulse(err) => write!(f, "{}", err),
+            Error::Regex(err) => write!(f, "{}", err),
+            Error::Message(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(err: ParseIntError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(err: TryFromIntError) -> Self {
+        Error::TryFrom(err)
+    }
+}
+
+impl From<SendError<ModuleMsg>> for Error {
+    fn from(err: SendError<ModuleMsg>) -> Self {
+        Error::Send(err)
+    }
+}
+
+impl From<PAErr> for Error {
+    fn from(err: PAErr) -> Self {
+        Error::Pulse(err)
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(err: regex::Error) -> Self {
+        Error::Regex(err)
+    }
+}
+
+impl From<String> for Error {
+    fn from(msg: String) -> Self {
+        Error::Message(msg)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(msg: &str) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
@@ -0,0 +1,65 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Minimal wireless extensions ioctl bindings, just enough to read the
+//! ESSID of an interface without pulling in a full `wireless-rs` style
+//! crate for a single syscall.
+
+use std::ffi::CString;
+use std::mem;
+use std::os::unix::io::RawFd;
+
+const SIOCGIWESSID: libc::c_ulong = 0x8B1B;
+const IW_ESSID_MAX_SIZE: usize = 32;
+
+#[repr(C)]
+struct IwPoint {
+    pointer: *mut libc::c_void,
+    length: u16,
+    flags: u16,
+}
+
+#[repr(C)]
+union IwRequestData {
+    essid: IwPoint,
+}
+
+#[repr(C)]
+struct IwReq {
+    ifr_name: [libc::c_char; libc::IFNAMSIZ],
+    u: IwRequestData,
+}
+
+pub fn get_essid(interface: &str) -> Option<String> {
+    let fd = open_socket()?;
+    let mut name = [0 as libc::c_char; libc::IFNAMSIZ];
+    let c_interface = CString::new(interface).ok()?;
+    for (dst, src) in name.iter_mut().zip(c_interface.as_bytes_with_nul()) {
+        *dst = *src as libc::c_char;
+    }
+    let mut buf = [0u8; IW_ESSID_MAX_SIZE + 1];
+    let mut req: IwReq = unsafe { mem::zeroed() };
+    req.ifr_name = name;
+    req.u.essid = IwPoint {
+        pointer: buf.as_mut_ptr() as *mut libc::c_void,
+        length: buf.len() as u16,
+        flags: 0,
+    };
+    let ret = unsafe { libc::ioctl(fd, SIOCGIWESSID, &mut req) };
+    unsafe { libc::close(fd) };
+    if ret < 0 {
+        return None;
+    }
+    let len = unsafe { req.u.essid.length } as usize;
+    String::from_utf8(buf[..len].to_vec()).ok()
+}
+
+fn open_socket() -> Option<RawFd> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0) };
+    if fd < 0 {
+        None
+    } else {
+        Some(fd)
+    }
+}
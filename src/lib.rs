@@ -2,196 +2,210 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use chrono::prelude::*;
+mod battery;
+mod brightness;
+mod cpu;
+mod date;
+mod discovery;
 mod error;
+mod ioctl;
+mod mic;
+mod module;
+mod nl_data;
+mod nl_event;
+mod output;
+mod pulse;
+mod sound;
+mod temperature;
+mod util;
+mod wireless;
+
+use battery::Battery;
+use brightness::Brightness;
+use cpu::Cpu;
+use date::Date;
 use error::Error;
-use std::convert::TryFrom;
-use std::fs::{self, File};
-use std::io::prelude::*;
-use std::io::{self, BufReader};
-
-const PROC_STAT: &'static str = "/proc/stat";
-const ENERGY_NOW: &'static str = "/sys/class/power_supply/BAT0/energy_now";
-const POWER_STATUS: &'static str = "/sys/class/power_supply/BAT0/status";
-const ENERGY_FULL_DESIGN: &'static str = "/sys/class/power_supply/BAT0/energy_full_design";
-const CORETEMP_PATH: &'static str = "/sys/devices/platform/coretemp.0/hwmon/hwmon7";
-const BACKLIGHT_PATH: &'static str =
-    "/sys/devices/pci0000:00/0000:00:02.0/drm/card0/card0-eDP-1/intel_backlight";
-const DEFAULT_FONT: &'static str = "+@fn=0;";
-const ICON_FONT: &'static str = "+@fn=1;";
-const DEFAULT_COLOR: &'static str = "+@fg=0;";
-const RED: &'static str = "+@fg=1;";
-const GREEN: &'static str = "+@fg=2;";
-
-pub struct Bar<'a> {
-    default_font: &'a str,
-    icon: &'a str,
-    default_color: &'a str,
-    red: &'a str,
-    green: &'a str,
-    prev_idle: i32,
-    prev_total: i32,
+use mic::Mic;
+use module::Bar;
+use output::{Block, ClickEvent};
+use pulse::Pulse;
+use serde::{Deserialize, Serialize};
+use sound::Sound;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use temperature::Temperature;
+use wireless::Wireless;
+
+/// Semantic color a module can attach to a value, resolved to a concrete
+/// escape code or hex string by whichever `Output` backend is active.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum Color {
+    Good,
+    Warning,
+    Critical,
 }
 
-impl<'a> Bar<'a> {
-    pub fn new() -> Self {
-        Bar {
-            default_font: DEFAULT_FONT,
-            icon: ICON_FONT,
-            default_color: DEFAULT_COLOR,
-            red: RED,
-            green: GREEN,
-            prev_idle: 0,
-            prev_total: 0,
-        }
-    }
+/// What a module sends on every update: its key, the rendered text and an
+/// optional color. One `ModuleMsg` becomes one rendered block.
+#[derive(Debug, Clone)]
+pub struct ModuleMsg(pub char, pub String, pub Option<Color>);
 
-    fn battery(self: &Self) -> Result<String, Error> {
-        let energy_full_design = read_and_parse(ENERGY_FULL_DESIGN)?;
-        let energy_now = read_and_parse(ENERGY_NOW)?;
-        let status = read_and_trim(POWER_STATUS)?;
-        let capacity = energy_full_design as u64;
-        let energy = energy_now as u64;
-        let battery_level = u32::try_from(100u64 * energy / capacity)?;
-        let mut color = match battery_level {
-            0..=10 => self.red,
-            _ => self.default_color,
-        };
-        if status == "Full" {
-            color = self.green
-        }
-        Ok(format!(
-            "{}{}{}{}{} {}%",
-            color,
-            self.icon,
-            get_battery_icon(&status, battery_level),
-            self.default_font,
-            self.default_color,
-            battery_level
-        ))
-    }
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModuleEntry {
+    pub key: char,
+    pub name: String,
+    /// Overrides whatever `Color` the module itself reports for its value
+    /// (e.g. force a module to always render `Good`-colored).
+    pub color: Option<Color>,
+    /// Overrides the module's `Bar::separator()` default for this instance.
+    pub separator: Option<bool>,
+}
 
-    fn cpu(self: &mut Self) -> Result<String, Error> {
-        let proc_stat = File::open(PROC_STAT)?;
-        let mut reader = BufReader::new(proc_stat);
-        let mut buf = String::new();
-        reader.read_line(&mut buf)?;
-        let mut data = buf.split_whitespace();
-        data.next();
-        let times: Vec<i32> = data
-            .map(|n| {
-                n.parse::<i32>()
-                    .expect(&format!("error while parsing the file \"{}\"", PROC_STAT))
-            })
-            .collect();
-        let idle = times[3] + times[4];
-        let total = times.iter().fold(0, |acc, i| acc + i);
-        let diff_idle = idle - self.prev_idle;
-        let diff_total = total - self.prev_total;
-        let usage = (1000 * (diff_total - diff_idle) / diff_total) / 10;
-        self.prev_idle = idle;
-        self.prev_total = total;
-        println!("{:#?}", usage);
-        Ok("eheh".to_string())
-    }
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    pub modules: Vec<ModuleEntry>,
+    /// One or more output backends to fan every rendered bar out to (e.g.
+    /// lemonbar on stdout and an i3bar FIFO at once).
+    pub outputs: Vec<output::Config>,
+    pub battery: Option<battery::Config>,
+    pub brightness: Option<brightness::Config>,
+    pub cpu: Option<cpu::Config>,
+    pub date: Option<date::Config>,
+    pub temperature: Option<temperature::Config>,
+    pub sound: Option<sound::Config>,
+    pub mic: Option<mic::Config>,
+    pub wireless: Option<wireless::Config>,
+}
 
-    fn core_temperature(self: &Self) -> Result<String, Error> {
-        let core_1 = read_and_parse(&format!("{}/temp2_input", CORETEMP_PATH))?;
-        let core_2 = read_and_parse(&format!("{}/temp3_input", CORETEMP_PATH))?;
-        let core_3 = read_and_parse(&format!("{}/temp4_input", CORETEMP_PATH))?;
-        let core_4 = read_and_parse(&format!("{}/temp5_input", CORETEMP_PATH))?;
-        let average =
-            (((core_1 + core_2 + core_3 + core_4) as f32 / 4f32) / 1000f32).round() as i32;
-        let mut color = self.default_color;
-        let icon = match average {
-            0..=50 => "󱃃",
-            51..=70 => "󰔏",
-            71..=100 => "󱃂",
-            _ => "󰸁",
-        };
-        if average > 75 {
-            color = self.red;
-        }
-        Ok(format!(
-            "{}{}{}{}{} {}°",
-            color, self.icon, icon, self.default_font, self.default_color, average
-        ))
-    }
+fn module_for(key: char, name: &str, config: &Config) -> Result<Box<dyn Bar + '_>, Error> {
+    let module: Box<dyn Bar + '_> = match name {
+        "battery" => Box::new(Battery::with_config(config)),
+        "brightness" => Box::new(Brightness::with_config(config)),
+        "cpu" => Box::new(Cpu::with_config(config)),
+        "date" => Box::new(Date::with_config(config)),
+        "temperature" => Box::new(Temperature::with_config(config)),
+        "sound" => Box::new(Sound::with_config(config)),
+        "mic" => Box::new(Mic::with_config(config)),
+        "wireless" => Box::new(Wireless::with_config(config)),
+        other => return Err(format!("key '{}': unknown module \"{}\"", key, other).into()),
+    };
+    Ok(module)
+}
 
-    fn brightness(self: &Self) -> Result<String, Error> {
-        let brightness = read_and_parse(&format!("{}/actual_brightness", BACKLIGHT_PATH))?;
-        let max_brightness = read_and_parse(&format!("{}/max_brightness", BACKLIGHT_PATH))?;
-        let percentage = 100 * brightness / max_brightness;
-        Ok(format!(
-            "{}󰃟{} {}%",
-            self.icon, self.default_font, percentage
-        ))
-    }
+/// Spawns one thread per configured module, one thread per configured
+/// output backend, and a dispatcher thread that owns the module receiver
+/// and fans its latest values out to every output. A startup barrier holds
+/// the dispatcher back until every output has finished `init`, so the
+/// first render is never split across a half-ready set of backends.
+pub fn run(config: Config) -> Result<(), Error> {
+    let pulse = Arc::new(Mutex::new(Pulse::new()?));
+    let (tx, rx) = mpsc::channel::<ModuleMsg>();
+    let (click_tx, click_rx) = mpsc::channel::<ClickEvent>();
 
-    pub fn update(self: &mut Self) -> Result<(), Error> {
-        let date_time = date_time();
-        let battery = self.battery()?;
-        let brightness = self.brightness()?;
-        let cpu = self.cpu()?;
-        let temperature = self.core_temperature()?;
-        println!(
-            "{}  {}  {}   {}",
-            temperature, brightness, battery, date_time
-        );
-        Ok(())
-    }
-}
+    let mut names = HashMap::new();
+    let mut separators = HashMap::new();
+    let mut values = HashMap::new();
+    let mut colors: HashMap<char, Option<Color>> = HashMap::new();
+    let mut configured_colors: HashMap<char, Option<Color>> = HashMap::new();
+    let mut click_routes: HashMap<char, Sender<ClickEvent>> = HashMap::new();
 
-fn read_and_trim<'a>(file: &'a str) -> Result<String, Error> {
-    let content = fs::read_to_string(file)
-        .map_err(|err| format!("error while reading the file \"{}\": {}", file, err))?;
-    Ok(content.trim().to_string())
-}
+    for entry in &config.modules {
+        let module = module_for(entry.key, &entry.name, &config)?;
+        names.insert(entry.key, module.name().to_string());
+        separators.insert(entry.key, entry.separator.or_else(|| module.separator()));
+        values.insert(entry.key, module.placeholder().to_string());
+        colors.insert(entry.key, None);
+        configured_colors.insert(entry.key, entry.color);
 
-fn read_and_parse<'a>(file: &'a str) -> Result<i32, Error> {
-    let content = read_and_trim(file)?;
-    let data = content
-        .parse::<i32>()
-        .map_err(|err| format!("error while parsing the file \"{}\": {}", file, err))?;
-    Ok(data)
-}
+        let (module_click_tx, module_click_rx) = mpsc::channel();
+        click_routes.insert(entry.key, module_click_tx);
 
-fn date_time() -> String {
-    let now = Local::now();
-    now.format("%a. %-e %B %Y, %-kh%M").to_string()
-}
+        let key = entry.key;
+        let main_config = config.clone();
+        let pulse = Arc::clone(&pulse);
+        let tx = tx.clone();
+        let run_fn = module.run_fn();
+        thread::spawn(move || {
+            if let Err(err) = run_fn(key, main_config, pulse, tx, module_click_rx) {
+                eprintln!("baru: module '{}' stopped: {}", key, err);
+            }
+        });
+    }
 
-fn get_battery_icon<'a>(state: &'a str, level: u32) -> &'static str {
-    match state {
-        "Full" => "󰁹",
-        "Discharging" => match level {
-            0..=9 => "󰂎",
-            10..=19 => "󰁺",
-            20..=29 => "󰁻",
-            30..=39 => "󰁼",
-            40..=49 => "󰁽",
-            50..=59 => "󰁾",
-            60..=69 => "󰁿",
-            70..=79 => "󰂀",
-            80..=89 => "󰂁",
-            90..=99 => "󰂂",
-            100 => "󰁹",
-            _ => "󱃍",
-        },
-        "Charging" => match level {
-            0..=9 => "󰢟",
-            10..=19 => "󰢜",
-            20..=29 => "󰂆",
-            30..=39 => "󰂇",
-            40..=49 => "󰂈",
-            50..=59 => "󰢝",
-            60..=69 => "󰂉",
-            70..=79 => "󰢞",
-            80..=89 => "󰂊",
-            90..=99 => "󰂋",
-            100 => "󰂅",
-            _ => "󱃍",
-        },
-        _ => "󱃍",
+    // Route click events read back by the output layer to the module that
+    // owns the clicked `instance`.
+    thread::spawn(move || {
+        for event in click_rx {
+            if let Some(key) = event.instance.chars().next() {
+                if let Some(sender) = click_routes.get(&key) {
+                    let _ = sender.send(event);
+                }
+            }
+        }
+    });
+
+    let barrier = Arc::new(Barrier::new(config.outputs.len() + 1));
+    let mut render_txs = Vec::with_capacity(config.outputs.len());
+    for output_config in &config.outputs {
+        let (render_tx, render_rx) = mpsc::channel::<Vec<Block>>();
+        render_txs.push(render_tx);
+        let output_config = output_config.clone();
+        let click_tx = click_tx.clone();
+        let barrier = Arc::clone(&barrier);
+        thread::spawn(move || {
+            let mut output = output::from_config(&output_config, click_tx);
+            let started = output.init();
+            // Reach the barrier whether init succeeded or not, otherwise a
+            // single misconfigured output would hang every other thread
+            // waiting for the first render.
+            barrier.wait();
+            if let Err(err) = started {
+                eprintln!("baru: output \"{:?}\" failed to start: {}", output_config.kind, err);
+                return;
+            }
+            for blocks in render_rx {
+                if let Err(err) = output.render(&blocks) {
+                    eprintln!("baru: output \"{:?}\" stopped: {}", output_config.kind, err);
+                    return;
+                }
+            }
+        });
     }
+
+    let order: Vec<char> = config.modules.iter().map(|e| e.key).collect();
+    let dispatcher = thread::spawn(move || -> Result<(), Error> {
+        barrier.wait();
+        for ModuleMsg(key, text, color) in rx {
+            values.insert(key, text);
+            colors.insert(key, color);
+            let blocks: Vec<Block> = order
+                .iter()
+                .map(|k| Block {
+                    full_text: values.get(k).cloned().unwrap_or_default(),
+                    name: names.get(k).cloned().unwrap_or_default(),
+                    instance: k.to_string(),
+                    // A user-configured color always wins over whatever the
+                    // module itself reported for this value.
+                    color: configured_colors
+                        .get(k)
+                        .copied()
+                        .flatten()
+                        .or_else(|| colors.get(k).copied().flatten()),
+                    background: None,
+                    separator: separators.get(k).copied().flatten(),
+                    min_width: None,
+                    align: None,
+                })
+                .collect();
+            for render_tx in &render_txs {
+                let _ = render_tx.send(blocks.clone());
+            }
+        }
+        Ok(())
+    });
+
+    dispatcher.join().map_err(|_| "dispatcher thread panicked")??;
+    Ok(())
 }
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::module::{Bar, RunPtr};
+use crate::output::ClickEvent;
+use crate::pulse::Pulse;
+use crate::{Config as MainConfig, ModuleMsg};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const PLACEHOLDER: &str = "-";
+const TICK_RATE: Duration = Duration::from_millis(50);
+const MUTE_LABEL: &str = ".mi";
+const LABEL: &str = "mic";
+const FORMAT: &str = "%l:%v";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    tick: Option<u32>,
+    placeholder: Option<String>,
+    label: Option<String>,
+    mute_label: Option<String>,
+    format: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct InternalConfig<'a> {
+    tick: Duration,
+    label: &'a str,
+    mute_label: &'a str,
+    format: &'a str,
+}
+
+impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
+    fn from(config: &'a MainConfig) -> Self {
+        let mut tick = TICK_RATE;
+        let mut label = LABEL;
+        let mut mute_label = MUTE_LABEL;
+        let mut format = FORMAT;
+        if let Some(c) = &config.mic {
+            if let Some(t) = c.tick {
+                tick = Duration::from_millis(t as u64)
+            }
+            if let Some(v) = &c.label {
+                label = v;
+            }
+            if let Some(v) = &c.mute_label {
+                mute_label = v;
+            }
+            if let Some(v) = &c.format {
+                format = v;
+            }
+        }
+        InternalConfig {
+            tick,
+            label,
+            mute_label,
+            format,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Mic<'a> {
+    placeholder: &'a str,
+    format: &'a str,
+}
+
+impl<'a> Mic<'a> {
+    pub fn with_config(config: &'a MainConfig) -> Self {
+        let mut placeholder = PLACEHOLDER;
+        let mut format = FORMAT;
+        if let Some(c) = &config.mic {
+            if let Some(p) = &c.placeholder {
+                placeholder = p
+            }
+            if let Some(v) = &c.format {
+                format = v;
+            }
+        }
+        Mic { placeholder, format }
+    }
+}
+
+impl<'a> Bar for Mic<'a> {
+    fn name(&self) -> &str {
+        "mic"
+    }
+
+    fn run_fn(&self) -> RunPtr {
+        run
+    }
+
+    fn placeholder(&self) -> &str {
+        self.placeholder
+    }
+
+    fn format(&self) -> &str {
+        self.format
+    }
+}
+
+// `source_data()` reflects PulseAudio's default source in real time (see
+// `Pulse`'s subscribe callback), so this tick only needs to read the cache
+// and format it; it never blocks on PulseAudio itself.
+pub fn run(
+    key: char,
+    main_config: MainConfig,
+    pulse: Arc<Mutex<Pulse>>,
+    tx: Sender<ModuleMsg>,
+    _: Receiver<ClickEvent>,
+) -> Result<(), Error> {
+    let config = InternalConfig::from(&main_config);
+    loop {
+        if let Some((volume, mute)) = pulse.lock().unwrap().source_data() {
+            let label = if mute { config.mute_label } else { config.label };
+            let text = config
+                .format
+                .replace("%l", label)
+                .replace("%v", &format!("{:3}%", volume));
+            tx.send(ModuleMsg(key, text, None))?;
+        }
+        thread::sleep(config.tick);
+    }
+}
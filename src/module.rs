@@ -0,0 +1,35 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use crate::output::ClickEvent;
+use crate::pulse::Pulse;
+use crate::{Config, ModuleMsg};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// Signature shared by every module's `run` loop. `click_rx` delivers click
+/// events routed back from the output layer for the module's own `instance`
+/// key; modules that don't care about clicks can drain or ignore it.
+pub type RunPtr = fn(
+    key: char,
+    main_config: Config,
+    pulse: Arc<Mutex<Pulse>>,
+    tx: Sender<ModuleMsg>,
+    click_rx: Receiver<ClickEvent>,
+) -> Result<(), Error>;
+
+/// A status bar module: something that periodically (or on event) produces
+/// a value and knows how it wants to be rendered.
+pub trait Bar {
+    fn name(&self) -> &str;
+    fn run_fn(&self) -> RunPtr;
+    fn placeholder(&self) -> &str;
+    fn format(&self) -> &str {
+        "%l:%v"
+    }
+    fn separator(&self) -> Option<bool> {
+        None
+    }
+}
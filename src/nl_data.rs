@@ -0,0 +1,48 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fs;
+
+const PROC_NET_WIRELESS: &str = "/proc/net/wireless";
+
+#[derive(Debug, Clone)]
+pub struct ConnectedData {
+    pub essid: Option<String>,
+    pub signal: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub enum WirelessState {
+    Connected(ConnectedData),
+    Disconnected,
+}
+
+/// Polls the current state of `interface`: link quality from
+/// `/proc/net/wireless` and the associated ESSID via `SIOCGIWESSID`.
+pub fn wireless_data(interface: &str) -> WirelessState {
+    let signal = read_signal(interface);
+    if signal.is_none() {
+        return WirelessState::Disconnected;
+    }
+    let essid = read_essid(interface);
+    WirelessState::Connected(ConnectedData { essid, signal })
+}
+
+fn read_signal(interface: &str) -> Option<i32> {
+    let content = fs::read_to_string(PROC_NET_WIRELESS).ok()?;
+    for line in content.lines().skip(2) {
+        let mut fields = line.split_whitespace();
+        let name = fields.next()?.trim_end_matches(':');
+        if name != interface {
+            continue;
+        }
+        let quality: f32 = fields.nth(1)?.trim_end_matches('.').parse().ok()?;
+        return Some(quality as i32);
+    }
+    None
+}
+
+fn read_essid(interface: &str) -> Option<String> {
+    crate::ioctl::get_essid(interface)
+}
@@ -0,0 +1,337 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Minimal netlink bindings, just enough to block on link up/down and
+//! nl80211 association/signal events instead of polling `/proc/net/wireless`
+//! on a timer. Mirrors the "bind just the syscalls we need" approach taken
+//! in `ioctl.rs` for the wireless extensions ioctl.
+
+use crate::error::Error;
+use std::mem;
+use std::os::unix::io::RawFd;
+use std::time::Duration;
+
+const NETLINK_ROUTE: libc::c_int = 0;
+const NETLINK_GENERIC: libc::c_int = 16;
+const RTMGRP_LINK: libc::c_uint = 1;
+
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+const CTRL_ATTR_MCAST_GROUPS: u16 = 7;
+const CTRL_ATTR_MCAST_GRP_NAME: u16 = 1;
+const CTRL_ATTR_MCAST_GRP_ID: u16 = 2;
+
+const NL80211_FAMILY_NAME: &str = "nl80211";
+const NL80211_MCAST_GROUPS: &[&str] = &["mlme", "config"];
+
+const NLMSG_HDRLEN: usize = 16;
+const GENLMSG_HDRLEN: usize = 4;
+const NLA_HDRLEN: usize = 4;
+
+/// A pair of blocking netlink sockets: one subscribed to `RTMGRP_LINK` (link
+/// up/down, always available) and, best-effort, one subscribed to nl80211's
+/// `mlme`/`config` multicast groups (association and signal changes). The
+/// nl80211 socket is optional because resolving its family id can fail on
+/// kernels without wireless support; the caller still gets link events.
+pub struct NlMonitor {
+    route_fd: RawFd,
+    genl_fd: Option<RawFd>,
+}
+
+impl NlMonitor {
+    pub fn new() -> Result<Self, Error> {
+        let route_fd = open_route_link_socket()?;
+        let genl_fd = open_nl80211_socket().ok();
+        Ok(NlMonitor { route_fd, genl_fd })
+    }
+
+    /// Blocks until a message is readable on either socket, or `timeout`
+    /// elapses. Reads and discards one pending message from each readable
+    /// socket (the caller doesn't need the message's content, just the fact
+    /// that something changed; any further queued message wakes the next
+    /// call right away) and
+    /// returns `true` if it woke up because of an event, `false` on
+    /// timeout so the caller can fall back to a periodic refresh.
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<bool, Error> {
+        let mut fds = vec![libc::pollfd {
+            fd: self.route_fd,
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        if let Some(genl_fd) = self.genl_fd {
+            fds.push(libc::pollfd {
+                fd: genl_fd,
+                events: libc::POLLIN,
+                revents: 0,
+            });
+        }
+        let timeout_ms = match timeout {
+            Some(d) => d.as_millis() as libc::c_int,
+            None => -1,
+        };
+        let ret = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout_ms) };
+        if ret < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        if ret == 0 {
+            return Ok(false);
+        }
+        let mut buf = [0u8; 4096];
+        for pfd in &fds {
+            if pfd.revents & libc::POLLIN != 0 {
+                unsafe {
+                    libc::recv(pfd.fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Drop for NlMonitor {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.route_fd);
+            if let Some(genl_fd) = self.genl_fd {
+                libc::close(genl_fd);
+            }
+        }
+    }
+}
+
+fn open_route_link_socket() -> Result<RawFd, Error> {
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_RAW, NETLINK_ROUTE) };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_groups = RTMGRP_LINK;
+    let ret = unsafe {
+        libc::bind(
+            fd,
+            &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+            mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        let err = std::io::Error::last_os_error();
+        unsafe { libc::close(fd) };
+        return Err(err.into());
+    }
+    Ok(fd)
+}
+
+fn open_nl80211_socket() -> Result<RawFd, Error> {
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_RAW, NETLINK_GENERIC) };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    let ret = unsafe {
+        libc::bind(
+            fd,
+            &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+            mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        let err = std::io::Error::last_os_error();
+        unsafe { libc::close(fd) };
+        return Err(err.into());
+    }
+    match resolve_nl80211_groups(fd) {
+        Ok(group_ids) if !group_ids.is_empty() => {
+            for id in group_ids {
+                unsafe {
+                    libc::setsockopt(
+                        fd,
+                        libc::SOL_NETLINK,
+                        libc::NETLINK_ADD_MEMBERSHIP,
+                        &id as *const u32 as *const libc::c_void,
+                        mem::size_of::<u32>() as libc::socklen_t,
+                    );
+                }
+            }
+            Ok(fd)
+        }
+        _ => {
+            unsafe { libc::close(fd) };
+            Err("unable to resolve nl80211 multicast groups".into())
+        }
+    }
+}
+
+/// Asks the kernel's generic netlink controller (`CTRL_CMD_GETFAMILY`) for
+/// the nl80211 family and returns the multicast group ids of `mlme` and
+/// `config`, the groups that carry association and signal-change events.
+fn resolve_nl80211_groups(fd: RawFd) -> Result<Vec<u32>, Error> {
+    send_getfamily_request(fd)?;
+    let mut buf = [0u8; 4096];
+    let len = unsafe { libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+    if len < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(parse_getfamily_response(&buf[..len as usize]))
+}
+
+fn send_getfamily_request(fd: RawFd) -> Result<(), Error> {
+    let name_attr = encode_attr(CTRL_ATTR_FAMILY_NAME, NL80211_FAMILY_NAME.as_bytes());
+    let payload_len = GENLMSG_HDRLEN + name_attr.len();
+    let total_len = NLMSG_HDRLEN + payload_len;
+
+    let mut msg = Vec::with_capacity(total_len);
+    msg.extend_from_slice(&(total_len as u32).to_ne_bytes());
+    msg.extend_from_slice(&GENL_ID_CTRL.to_ne_bytes());
+    msg.extend_from_slice(&(libc::NLM_F_REQUEST as u16).to_ne_bytes());
+    msg.extend_from_slice(&1u32.to_ne_bytes()); // seq
+    msg.extend_from_slice(&0u32.to_ne_bytes()); // pid
+    msg.push(CTRL_CMD_GETFAMILY);
+    msg.push(1); // version
+    msg.extend_from_slice(&0u16.to_ne_bytes()); // reserved
+    msg.extend_from_slice(&name_attr);
+
+    let ret = unsafe { libc::send(fd, msg.as_ptr() as *const libc::c_void, msg.len(), 0) };
+    if ret < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+fn encode_attr(attr_type: u16, payload: &[u8]) -> Vec<u8> {
+    let len = NLA_HDRLEN + payload.len();
+    let mut attr = Vec::with_capacity(align4(len));
+    attr.extend_from_slice(&(len as u16).to_ne_bytes());
+    attr.extend_from_slice(&attr_type.to_ne_bytes());
+    attr.extend_from_slice(payload);
+    attr.resize(align4(len), 0);
+    attr
+}
+
+fn align4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn parse_getfamily_response(msg: &[u8]) -> Vec<u32> {
+    if msg.len() < NLMSG_HDRLEN + GENLMSG_HDRLEN {
+        return Vec::new();
+    }
+    let attrs = &msg[NLMSG_HDRLEN + GENLMSG_HDRLEN..];
+    let mut family_id: Option<u16> = None;
+    let mut group_ids = Vec::new();
+    for_each_attr(attrs, |attr_type, payload| match attr_type {
+        CTRL_ATTR_FAMILY_ID if payload.len() >= 2 => {
+            family_id = Some(u16::from_ne_bytes([payload[0], payload[1]]));
+        }
+        CTRL_ATTR_MCAST_GROUPS => {
+            for_each_attr(payload, |_, group| {
+                let mut name = None;
+                let mut id = None;
+                for_each_attr(group, |gattr_type, gpayload| match gattr_type {
+                    CTRL_ATTR_MCAST_GRP_NAME => {
+                        name = std::str::from_utf8(gpayload)
+                            .ok()
+                            .map(|s| s.trim_end_matches('\0').to_string());
+                    }
+                    CTRL_ATTR_MCAST_GRP_ID if gpayload.len() >= 4 => {
+                        id = Some(u32::from_ne_bytes([
+                            gpayload[0],
+                            gpayload[1],
+                            gpayload[2],
+                            gpayload[3],
+                        ]));
+                    }
+                    _ => {}
+                });
+                if let (Some(name), Some(id)) = (name, id) {
+                    if NL80211_MCAST_GROUPS.contains(&name.as_str()) {
+                        group_ids.push(id);
+                    }
+                }
+            });
+        }
+        _ => {}
+    });
+    let _ = family_id;
+    group_ids
+}
+
+fn for_each_attr(buf: &[u8], mut f: impl FnMut(u16, &[u8])) {
+    let mut offset = 0;
+    while offset + NLA_HDRLEN <= buf.len() {
+        let len = u16::from_ne_bytes([buf[offset], buf[offset + 1]]) as usize;
+        let attr_type = u16::from_ne_bytes([buf[offset + 2], buf[offset + 3]]);
+        if len < NLA_HDRLEN || offset + len > buf.len() {
+            break;
+        }
+        f(attr_type, &buf[offset + NLA_HDRLEN..offset + len]);
+        offset += align4(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nested_attr(attr_type: u16, children: &[Vec<u8>]) -> Vec<u8> {
+        let payload: Vec<u8> = children.concat();
+        encode_attr(attr_type, &payload)
+    }
+
+    #[test]
+    fn for_each_attr_visits_every_top_level_attribute() {
+        let buf = [
+            encode_attr(CTRL_ATTR_FAMILY_ID, &135u16.to_ne_bytes()),
+            encode_attr(CTRL_ATTR_FAMILY_NAME, b"nl80211\0"),
+        ]
+        .concat();
+        let mut seen = Vec::new();
+        for_each_attr(&buf, |attr_type, payload| seen.push((attr_type, payload.to_vec())));
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].0, CTRL_ATTR_FAMILY_ID);
+        assert_eq!(seen[1].0, CTRL_ATTR_FAMILY_NAME);
+    }
+
+    #[test]
+    fn parse_getfamily_response_extracts_mlme_and_config_group_ids() {
+        let mlme_group = nested_attr(
+            0,
+            &[
+                encode_attr(CTRL_ATTR_MCAST_GRP_NAME, b"mlme\0"),
+                encode_attr(CTRL_ATTR_MCAST_GRP_ID, &7u32.to_ne_bytes()),
+            ],
+        );
+        let config_group = nested_attr(
+            1,
+            &[
+                encode_attr(CTRL_ATTR_MCAST_GRP_NAME, b"config\0"),
+                encode_attr(CTRL_ATTR_MCAST_GRP_ID, &9u32.to_ne_bytes()),
+            ],
+        );
+        let other_group = nested_attr(
+            2,
+            &[
+                encode_attr(CTRL_ATTR_MCAST_GRP_NAME, b"scan\0"),
+                encode_attr(CTRL_ATTR_MCAST_GRP_ID, &3u32.to_ne_bytes()),
+            ],
+        );
+        let groups_attr = nested_attr(
+            CTRL_ATTR_MCAST_GROUPS,
+            &[mlme_group, config_group, other_group],
+        );
+        let header = [0u8; NLMSG_HDRLEN + GENLMSG_HDRLEN];
+        let msg = [header.to_vec(), groups_attr].concat();
+
+        let group_ids = parse_getfamily_response(&msg);
+        assert_eq!(group_ids, vec![7, 9]);
+    }
+
+    #[test]
+    fn parse_getfamily_response_returns_empty_on_a_truncated_message() {
+        assert!(parse_getfamily_response(&[0u8; 4]).is_empty());
+    }
+}
@@ -0,0 +1,303 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+const DEFAULT_FONT: &str = "+@fn=0;";
+const ICON_FONT: &str = "+@fn=1;";
+const DEFAULT_COLOR: &str = "+@fg=0;";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Lemonbar,
+    I3bar,
+    Fifo,
+}
+
+/// One entry of the `outputs` list in `MainConfig`; `type` picks the
+/// backend via [`from_config`], the other fields are backend-specific
+/// (`path` only matters to `Fifo`).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    #[serde(rename = "type")]
+    pub kind: Kind,
+    pub path: Option<String>,
+}
+
+/// One module's rendered value, handed to the output layer by the
+/// dispatch loop. `name`/`instance` mirror the i3bar protocol fields so the
+/// lemonbar renderer can just ignore what it doesn't need. `color` is the
+/// module's semantic color, resolved by each backend to whatever it needs
+/// (a lemonbar escape, an i3bar hex string, ...) since the same block is
+/// fanned out to every configured output.
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub full_text: String,
+    pub name: String,
+    pub instance: String,
+    pub color: Option<crate::Color>,
+    pub background: Option<String>,
+    pub separator: Option<bool>,
+    pub min_width: Option<u32>,
+    pub align: Option<String>,
+}
+
+/// A click event read back from the output device (currently only i3bar
+/// sends these) and routed to the module that owns `instance`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClickEvent {
+    pub name: String,
+    pub instance: String,
+    pub button: u32,
+    #[serde(default)]
+    pub x: i32,
+    #[serde(default)]
+    pub y: i32,
+}
+
+/// A rendering backend. Each output runs on its own thread, which calls
+/// `init` once at startup and `render` every time the dispatch loop sends it
+/// the blocks for a new module value.
+pub trait Output {
+    fn init(&mut self) -> Result<(), Error>;
+    fn render(&mut self, blocks: &[Block]) -> Result<(), Error>;
+}
+
+/// Maps a module's semantic `Color` to whatever the active backend expects:
+/// a lemonbar `+@fg=N;` escape or an i3bar hex string.
+pub fn resolve_color(kind: Kind, color: Option<crate::Color>) -> Option<String> {
+    use crate::Color::*;
+    match (kind, color?) {
+        // Fifo shares Lemonbar's plain-text escapes (see `plain_text_line`).
+        (Kind::Lemonbar, Good) | (Kind::Fifo, Good) => Some("+@fg=2;".to_string()),
+        (Kind::Lemonbar, Warning | Critical) | (Kind::Fifo, Warning | Critical) => {
+            Some("+@fg=1;".to_string())
+        }
+        (Kind::I3bar, Good) => Some("#00ff00".to_string()),
+        (Kind::I3bar, Warning) => Some("#ffff00".to_string()),
+        (Kind::I3bar, Critical) => Some("#ff0000".to_string()),
+    }
+}
+
+/// Picks a backend by its `type` string, the way [`crate::module_for`] picks
+/// a module by its `name`.
+pub fn from_config(config: &Config, click_tx: Sender<ClickEvent>) -> Box<dyn Output + Send> {
+    match config.kind {
+        Kind::Lemonbar => Box::new(Lemonbar::new()),
+        Kind::I3bar => Box::new(I3bar::new(click_tx)),
+        Kind::Fifo => Box::new(Fifo::new(config.path.clone().unwrap_or_default())),
+    }
+}
+
+fn plain_text_line(blocks: &[Block]) -> String {
+    blocks
+        .iter()
+        .map(|b| {
+            let color = resolve_color(Kind::Lemonbar, b.color).unwrap_or_else(|| DEFAULT_COLOR.to_string());
+            format!("{}{}{}{}", color, ICON_FONT, b.full_text, DEFAULT_FONT)
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// The original lemonbar-style renderer: plain text with `+@fn=`/`+@fg=`
+/// escapes, one line per refresh, no click support.
+pub struct Lemonbar;
+
+impl Lemonbar {
+    pub fn new() -> Self {
+        Lemonbar
+    }
+}
+
+impl Output for Lemonbar {
+    fn init(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn render(&mut self, blocks: &[Block]) -> Result<(), Error> {
+        println!("{}", plain_text_line(blocks));
+        Ok(())
+    }
+}
+
+/// Writes the same plain-text line `Lemonbar` prints to a named pipe
+/// instead of stdout, for setups that read the bar's output from a FIFO
+/// (e.g. a second process feeding it to a different status bar).
+pub struct Fifo {
+    path: String,
+    file: Option<std::fs::File>,
+}
+
+impl Fifo {
+    pub fn new(path: String) -> Self {
+        Fifo { path, file: None }
+    }
+}
+
+impl Output for Fifo {
+    /// Only creates the pipe special file; deliberately does *not* open it.
+    /// Opening a FIFO for writing blocks until a reader attaches, which
+    /// would stall the startup barrier (and every other output waiting on
+    /// it) for as long as nothing is reading the pipe. The open happens
+    /// lazily on the first `render` instead, inside this output's own
+    /// thread, where blocking only holds up this one backend.
+    fn init(&mut self) -> Result<(), Error> {
+        if self.path.is_empty() {
+            return Err("fifo output requires a \"path\"".into());
+        }
+        let c_path = std::ffi::CString::new(self.path.as_str())
+            .map_err(|_| "fifo path must not contain a NUL byte")?;
+        // EEXIST just means a previous run already created the pipe.
+        let ret = unsafe { libc::mkfifo(c_path.as_ptr(), 0o644) };
+        if ret < 0 {
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::EEXIST) {
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, blocks: &[Block]) -> Result<(), Error> {
+        if self.file.is_none() {
+            // Blocks until a reader opens the other end, by FIFO semantics.
+            self.file = Some(OpenOptions::new().write(true).open(&self.path)?);
+        }
+        if let Some(file) = &mut self.file {
+            writeln!(file, "{}", plain_text_line(blocks))?;
+        }
+        Ok(())
+    }
+}
+
+/// Speaks the i3bar JSON protocol (swaybar/i3bar compatible): a header, an
+/// opening `[`, then one JSON array of blocks per line. Also spawns a
+/// reader thread on stdin to pick up `click_events` and forward them to
+/// `click_tx`, from which a routing thread in [`crate::run`] forwards them
+/// to the owning module.
+pub struct I3bar {
+    started: bool,
+    click_tx: Sender<ClickEvent>,
+}
+
+impl I3bar {
+    pub fn new(click_tx: Sender<ClickEvent>) -> Self {
+        I3bar {
+            started: false,
+            click_tx,
+        }
+    }
+
+    fn spawn_click_reader(&self) {
+        let click_tx = self.click_tx.clone();
+        thread::spawn(move || {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines() {
+                let line = match line {
+                    Ok(l) => l,
+                    Err(_) => continue,
+                };
+                // i3bar prefixes the stream with `[` and separates events
+                // with a leading `,`; strip whatever isn't a JSON object.
+                let trimmed = line.trim().trim_start_matches(',').trim_start_matches('[');
+                if trimmed.is_empty() || trimmed == "]" {
+                    continue;
+                }
+                if let Ok(event) = serde_json::from_str::<ClickEvent>(trimmed) {
+                    let _ = click_tx.send(event);
+                }
+            }
+        });
+    }
+}
+
+impl Output for I3bar {
+    fn init(&mut self) -> Result<(), Error> {
+        println!("{{\"version\":1,\"click_events\":true}}");
+        println!("[");
+        self.spawn_click_reader();
+        self.started = true;
+        Ok(())
+    }
+
+    fn render(&mut self, blocks: &[Block]) -> Result<(), Error> {
+        let json_blocks = blocks
+            .iter()
+            .map(block_to_json)
+            .collect::<Vec<_>>()
+            .join(",");
+        println!("[{}],", json_blocks);
+        Ok(())
+    }
+}
+
+fn block_to_json(block: &Block) -> String {
+    let mut fields = vec![
+        format!("\"full_text\":{}", json_string(&block.full_text)),
+        format!("\"name\":{}", json_string(&block.name)),
+        format!("\"instance\":{}", json_string(&block.instance)),
+    ];
+    if let Some(color) = resolve_color(Kind::I3bar, block.color) {
+        fields.push(format!("\"color\":{}", json_string(&color)));
+    }
+    if let Some(background) = &block.background {
+        fields.push(format!("\"background\":{}", json_string(background)));
+    }
+    if let Some(separator) = block.separator {
+        fields.push(format!("\"separator\":{}", separator));
+    }
+    if let Some(min_width) = block.min_width {
+        fields.push(format!("\"min_width\":{}", min_width));
+    }
+    if let Some(align) = &block.align {
+        fields.push(format!("\"align\":{}", json_string(align)));
+    }
+    format!("{{{}}}", fields.join(","))
+}
+
+fn json_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn resolve_color_covers_every_kind() {
+        assert_eq!(resolve_color(Kind::Lemonbar, None), None);
+        assert_eq!(resolve_color(Kind::Lemonbar, Some(Color::Good)), Some("+@fg=2;".to_string()));
+        assert_eq!(resolve_color(Kind::Lemonbar, Some(Color::Critical)), Some("+@fg=1;".to_string()));
+        assert_eq!(resolve_color(Kind::Fifo, Some(Color::Good)), Some("+@fg=2;".to_string()));
+        assert_eq!(resolve_color(Kind::Fifo, Some(Color::Warning)), Some("+@fg=1;".to_string()));
+        assert_eq!(resolve_color(Kind::I3bar, Some(Color::Critical)), Some("#ff0000".to_string()));
+    }
+
+    #[test]
+    fn block_to_json_includes_only_set_fields() {
+        let block = Block {
+            full_text: "50%".to_string(),
+            name: "battery".to_string(),
+            instance: "b".to_string(),
+            color: Some(Color::Critical),
+            background: None,
+            separator: Some(false),
+            min_width: None,
+            align: None,
+        };
+        let json = block_to_json(&block);
+        assert_eq!(
+            json,
+            "{\"full_text\":\"50%\",\"name\":\"battery\",\"instance\":\"b\",\"color\":\"#ff0000\",\"separator\":false}"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use libpulse_binding::callbacks::ListResult;
+use libpulse_binding::context::subscribe::{Facility, InterestMaskSet};
+use libpulse_binding::context::{Context, FlagSet as ContextFlagSet, State as ContextState};
+use libpulse_binding::mainloop::threaded::Mainloop;
+use libpulse_binding::proplist::Proplist;
+use libpulse_binding::volume::Volume;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const APP_NAME: &str = "baru";
+
+#[derive(Debug, Clone, Copy, Default)]
+struct AudioData {
+    volume: u32,
+    mute: bool,
+}
+
+/// Thin wrapper around a PulseAudio threaded mainloop, kept alive for the
+/// whole program and shared between modules behind an `Arc<Mutex<_>>`. The
+/// context subscribes to sink/source/server change events and re-queries
+/// the default sink/source whenever one fires, so `sink_data`/`source_data`
+/// always return the latest cached value without blocking.
+pub struct Pulse {
+    mainloop: Rc<RefCell<Mainloop>>,
+    context: Rc<RefCell<Context>>,
+    sink: Rc<RefCell<Option<AudioData>>>,
+    source: Rc<RefCell<Option<AudioData>>>,
+}
+
+impl Pulse {
+    pub fn new() -> Result<Self, Error> {
+        let proplist = Proplist::new().ok_or("unable to create pulseaudio proplist")?;
+        let mainloop = Rc::new(RefCell::new(
+            Mainloop::new().ok_or("unable to create pulseaudio mainloop")?,
+        ));
+        let context = Rc::new(RefCell::new(
+            Context::new_with_proplist(&*mainloop.borrow(), APP_NAME, &proplist)
+                .ok_or("unable to create pulseaudio context")?,
+        ));
+        context
+            .borrow_mut()
+            .connect(None, ContextFlagSet::NOFLAGS, None)?;
+        mainloop.borrow_mut().start()?;
+
+        loop {
+            match context.borrow().get_state() {
+                ContextState::Ready => break,
+                ContextState::Failed | ContextState::Terminated => {
+                    return Err("pulseaudio context failed to connect".into())
+                }
+                _ => {}
+            }
+        }
+
+        let sink = Rc::new(RefCell::new(None));
+        let source = Rc::new(RefCell::new(None));
+        refresh_default_sink(&mainloop, &context, &sink);
+        refresh_default_source(&mainloop, &context, &source);
+        subscribe(&mainloop, &context, &sink, &source);
+
+        Ok(Pulse {
+            mainloop,
+            context,
+            sink,
+            source,
+        })
+    }
+
+    pub fn sink_data(&mut self) -> Option<(u32, bool)> {
+        self.sink.borrow().map(|d| (d.volume, d.mute))
+    }
+
+    pub fn source_data(&mut self) -> Option<(u32, bool)> {
+        self.source.borrow().map(|d| (d.volume, d.mute))
+    }
+}
+
+impl Drop for Pulse {
+    fn drop(&mut self) {
+        self.mainloop.borrow_mut().stop();
+        self.context.borrow_mut().disconnect();
+    }
+}
+
+/// Registers a `set_subscribe_callback` for sink/source/server events and
+/// turns subscription on via `context.subscribe`. A `Server` event means the
+/// default sink or source itself may have changed, so both are re-resolved;
+/// a `Sink`/`Source` event means some sink/source's state changed, which may
+/// or may not be the default one, so the default is just re-queried rather
+/// than trying to match indices.
+fn subscribe(
+    mainloop: &Rc<RefCell<Mainloop>>,
+    context: &Rc<RefCell<Context>>,
+    sink: &Rc<RefCell<Option<AudioData>>>,
+    source: &Rc<RefCell<Option<AudioData>>>,
+) {
+    let mainloop_cb = Rc::clone(mainloop);
+    let context_cb = Rc::clone(context);
+    let sink_cb = Rc::clone(sink);
+    let source_cb = Rc::clone(source);
+    context
+        .borrow_mut()
+        .set_subscribe_callback(Some(Box::new(move |facility, _operation, _index| {
+            match facility {
+                Some(Facility::Sink) => refresh_default_sink(&mainloop_cb, &context_cb, &sink_cb),
+                Some(Facility::Source) => {
+                    refresh_default_source(&mainloop_cb, &context_cb, &source_cb)
+                }
+                Some(Facility::Server) => {
+                    refresh_default_sink(&mainloop_cb, &context_cb, &sink_cb);
+                    refresh_default_source(&mainloop_cb, &context_cb, &source_cb);
+                }
+                _ => {}
+            }
+        })));
+
+    mainloop.borrow_mut().lock();
+    context
+        .borrow_mut()
+        .subscribe(
+            InterestMaskSet::SINK | InterestMaskSet::SOURCE | InterestMaskSet::SERVER,
+            |_| {},
+        );
+    mainloop.borrow_mut().unlock();
+}
+
+/// Looks up the server's current default sink name, then queries that
+/// sink's volume/mute. Two round trips, but it's only done at startup and
+/// on a `Sink`/`Server` subscription event, never on the polling path.
+fn refresh_default_sink(
+    mainloop: &Rc<RefCell<Mainloop>>,
+    context: &Rc<RefCell<Context>>,
+    sink: &Rc<RefCell<Option<AudioData>>>,
+) {
+    let context_for_lookup = Rc::clone(context);
+    let sink_cell = Rc::clone(sink);
+    mainloop.borrow_mut().lock();
+    context.borrow().introspect().get_server_info(move |info| {
+        let name = match &info.default_sink_name {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let sink_cell = Rc::clone(&sink_cell);
+        context_for_lookup
+            .borrow()
+            .introspect()
+            .get_sink_info_by_name(&name, move |res| {
+                if let ListResult::Item(info) = res {
+                    *sink_cell.borrow_mut() = Some(AudioData {
+                        volume: info.volume.avg().0 * 100 / Volume::NORMAL.0,
+                        mute: info.mute,
+                    });
+                }
+            });
+    });
+    mainloop.borrow_mut().unlock();
+}
+
+/// Same as `refresh_default_sink` but for the default source (capture
+/// device), which is what the `mic` module reads.
+fn refresh_default_source(
+    mainloop: &Rc<RefCell<Mainloop>>,
+    context: &Rc<RefCell<Context>>,
+    source: &Rc<RefCell<Option<AudioData>>>,
+) {
+    let context_for_lookup = Rc::clone(context);
+    let source_cell = Rc::clone(source);
+    mainloop.borrow_mut().lock();
+    context.borrow().introspect().get_server_info(move |info| {
+        let name = match &info.default_source_name {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let source_cell = Rc::clone(&source_cell);
+        context_for_lookup
+            .borrow()
+            .introspect()
+            .get_source_info_by_name(&name, move |res| {
+                if let ListResult::Item(info) = res {
+                    *source_cell.borrow_mut() = Some(AudioData {
+                        volume: info.volume.avg().0 * 100 / Volume::NORMAL.0,
+                        mute: info.mute,
+                    });
+                }
+            });
+    });
+    mainloop.borrow_mut().unlock();
+}
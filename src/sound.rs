@@ -4,10 +4,11 @@
 
 use crate::error::Error;
 use crate::module::{Bar, RunPtr};
+use crate::output::ClickEvent;
 use crate::pulse::Pulse;
 use crate::{Config as MainConfig, ModuleMsg};
 use serde::{Deserialize, Serialize};
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -33,6 +34,7 @@ pub struct InternalConfig<'a> {
     tick: Duration,
     label: &'a str,
     mute_label: &'a str,
+    format: &'a str,
 }
 
 impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
@@ -40,6 +42,7 @@ impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
         let mut tick = TICK_RATE;
         let mut label = LABEL;
         let mut mute_label = MUTE_LABEL;
+        let mut format = FORMAT;
         if let Some(c) = &config.sound {
             if let Some(t) = c.tick {
                 tick = Duration::from_millis(t as u64)
@@ -50,11 +53,15 @@ impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
             if let Some(v) = &c.mute_label {
                 mute_label = v;
             }
+            if let Some(v) = &c.format {
+                format = v;
+            }
         }
         InternalConfig {
             tick,
             label,
             mute_label,
+            format,
         }
     }
 }
@@ -62,7 +69,6 @@ impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
 #[derive(Debug)]
 pub struct Sound<'a> {
     placeholder: &'a str,
-    config: &'a MainConfig,
     format: &'a str,
 }
 
@@ -78,11 +84,7 @@ impl<'a> Sound<'a> {
                 format = v;
             }
         }
-        Sound {
-            placeholder,
-            config,
-            format,
-        }
+        Sound { placeholder, format }
     }
 }
 
@@ -109,19 +111,17 @@ pub fn run(
     main_config: MainConfig,
     pulse: Arc<Mutex<Pulse>>,
     tx: Sender<ModuleMsg>,
+    _: Receiver<ClickEvent>,
 ) -> Result<(), Error> {
     let config = InternalConfig::from(&main_config);
     loop {
-        if let Some(data) = pulse.lock().unwrap().sink_data() {
-            let label = match data.1 {
-                true => config.mute_label,
-                false => config.label,
-            };
-            tx.send(ModuleMsg(
-                key,
-                Some(format!("{:3}%", data.0)),
-                Some(label.to_string()),
-            ))?;
+        if let Some((volume, mute)) = pulse.lock().unwrap().sink_data() {
+            let label = if mute { config.mute_label } else { config.label };
+            let text = config
+                .format
+                .replace("%l", label)
+                .replace("%v", &format!("{:3}%", volume));
+            tx.send(ModuleMsg(key, text, None))?;
         }
         thread::sleep(config.tick);
     }
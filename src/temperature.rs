@@ -0,0 +1,231 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discovery::{self, HwmonSensor};
+use crate::error::Error;
+use crate::module::{Bar, RunPtr};
+use crate::output::ClickEvent;
+use crate::pulse::Pulse;
+use crate::util::read_and_parse;
+use crate::{Color, Config as MainConfig, ModuleMsg};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const PLACEHOLDER: &str = "-";
+const TICK_RATE: Duration = Duration::from_secs(2);
+const HWMON_NAMES: &[&str] = &["coretemp", "k10temp"];
+const UNIT: Unit = Unit::Celsius;
+const WARNING_THRESHOLD: i32 = 70;
+const CRITICAL_THRESHOLD: i32 = 75;
+const WARNING_COLOR: Color = Color::Warning;
+const CRITICAL_COLOR: Color = Color::Critical;
+
+/// The unit the averaged reading is converted to before formatting and
+/// before it's compared against `warning`/`critical`. Thresholds and colors
+/// are always expressed in whichever unit is configured.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+pub enum Unit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    tick: Option<u32>,
+    placeholder: Option<String>,
+    unit: Option<Unit>,
+    warning: Option<i32>,
+    critical: Option<i32>,
+    warning_color: Option<Color>,
+    critical_color: Option<Color>,
+}
+
+#[derive(Debug)]
+pub struct InternalConfig {
+    tick: Duration,
+    sensor: Option<HwmonSensor>,
+    unit: Unit,
+    warning: i32,
+    critical: i32,
+    warning_color: Color,
+    critical_color: Color,
+}
+
+impl From<&MainConfig> for InternalConfig {
+    fn from(config: &MainConfig) -> Self {
+        let mut tick = TICK_RATE;
+        let mut unit = UNIT;
+        let mut warning = WARNING_THRESHOLD;
+        let mut critical = CRITICAL_THRESHOLD;
+        let mut warning_color = WARNING_COLOR;
+        let mut critical_color = CRITICAL_COLOR;
+        if let Some(c) = &config.temperature {
+            if let Some(t) = c.tick {
+                tick = Duration::from_millis(t as u64)
+            }
+            if let Some(u) = c.unit {
+                unit = u
+            }
+            if let Some(w) = c.warning {
+                warning = w
+            }
+            if let Some(cr) = c.critical {
+                critical = cr
+            }
+            if let Some(col) = c.warning_color {
+                warning_color = col
+            }
+            if let Some(col) = c.critical_color {
+                critical_color = col
+            }
+        }
+        InternalConfig {
+            tick,
+            sensor: discovery::discover_hwmon(HWMON_NAMES),
+            unit,
+            warning,
+            critical,
+            warning_color,
+            critical_color,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Temperature<'a> {
+    placeholder: &'a str,
+}
+
+impl<'a> Temperature<'a> {
+    pub fn with_config(config: &'a MainConfig) -> Self {
+        let mut placeholder = PLACEHOLDER;
+        if let Some(c) = &config.temperature {
+            if let Some(p) = &c.placeholder {
+                placeholder = p
+            }
+        }
+        Temperature { placeholder }
+    }
+}
+
+impl<'a> Bar for Temperature<'a> {
+    fn name(&self) -> &str {
+        "temperature"
+    }
+
+    fn run_fn(&self) -> RunPtr {
+        run
+    }
+
+    fn placeholder(&self) -> &str {
+        self.placeholder
+    }
+}
+
+pub fn run(
+    key: char,
+    main_config: MainConfig,
+    _: Arc<Mutex<Pulse>>,
+    tx: Sender<ModuleMsg>,
+    _: Receiver<ClickEvent>,
+) -> Result<(), Error> {
+    let config = InternalConfig::from(&main_config);
+    let sensor = config
+        .sensor
+        .ok_or("no coretemp/k10temp hwmon device found under /sys/class/hwmon")?;
+    loop {
+        let (text, color) = core_temperature(&sensor, &config)?;
+        tx.send(ModuleMsg(key, text, color))?;
+        thread::sleep(config.tick);
+    }
+}
+
+fn core_temperature(
+    sensor: &HwmonSensor,
+    config: &InternalConfig,
+) -> Result<(String, Option<Color>), Error> {
+    let millidegrees: i32 = sensor
+        .inputs
+        .iter()
+        .map(|input| read_and_parse(&input.to_string_lossy()))
+        .collect::<Result<Vec<i32>, Error>>()?
+        .into_iter()
+        .sum();
+    let celsius = ((millidegrees as f32 / sensor.inputs.len() as f32) / 1000f32).round() as i32;
+    // Icon breakpoints are a physical heat scale, so they stay in Celsius
+    // regardless of the unit the user wants displayed.
+    let icon = match celsius {
+        0..=50 => "󱃃",
+        51..=70 => "󰔏",
+        71..=100 => "󱃂",
+        _ => "󰸁",
+    };
+    let value = match config.unit {
+        Unit::Celsius => celsius,
+        Unit::Fahrenheit => celsius * 9 / 5 + 32,
+    };
+    let color = match value {
+        _ if value > config.critical => Some(config.critical_color),
+        _ if value > config.warning => Some(config.warning_color),
+        _ => None,
+    };
+    Ok((format!("{} {}°", icon, value), color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensor_with(dir: &std::path::Path, millidegrees: &[&str]) -> HwmonSensor {
+        std::fs::create_dir_all(dir).unwrap();
+        let inputs = millidegrees
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let path = dir.join(format!("temp{}_input", i + 1));
+                std::fs::write(&path, value).unwrap();
+                path
+            })
+            .collect();
+        HwmonSensor { inputs }
+    }
+
+    fn config_with(unit: Unit, warning: i32, critical: i32) -> InternalConfig {
+        InternalConfig {
+            tick: TICK_RATE,
+            sensor: None,
+            unit,
+            warning,
+            critical,
+            warning_color: WARNING_COLOR,
+            critical_color: CRITICAL_COLOR,
+        }
+    }
+
+    #[test]
+    fn averages_and_converts_to_fahrenheit() {
+        let dir = std::env::temp_dir().join(format!("baru-test-temp-f-{}", std::process::id()));
+        let sensor = sensor_with(&dir, &["40000", "50000"]);
+        // 70°C/75°C expressed in Fahrenheit, the configured unit
+        let config = config_with(Unit::Fahrenheit, 158, 167);
+        let (text, color) = core_temperature(&sensor, &config).unwrap();
+        // average 45°C -> 113°F, below both thresholds
+        assert_eq!(text, "󱃃 113°");
+        assert!(color.is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn applies_warning_and_critical_colors_in_the_configured_unit() {
+        let dir = std::env::temp_dir().join(format!("baru-test-temp-c-{}", std::process::id()));
+        let sensor = sensor_with(&dir, &["80000"]);
+        let config = config_with(Unit::Celsius, 70, 75);
+        let (_, color) = core_temperature(&sensor, &config).unwrap();
+        assert_eq!(color, Some(CRITICAL_COLOR));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,20 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::error::Error;
+use std::fs;
+
+pub fn read_and_trim(file: &str) -> Result<String, Error> {
+    let content = fs::read_to_string(file)
+        .map_err(|err| format!("error while reading the file \"{}\": {}", file, err))?;
+    Ok(content.trim().to_string())
+}
+
+pub fn read_and_parse(file: &str) -> Result<i32, Error> {
+    let content = read_and_trim(file)?;
+    let data = content
+        .parse::<i32>()
+        .map_err(|err| format!("error while parsing the file \"{}\": {}", file, err))?;
+    Ok(data)
+}
@@ -2,13 +2,17 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::discovery;
 use crate::error::Error;
-use crate::module::{BaruMod, RunPtr};
+use crate::module::{Bar, RunPtr};
 use crate::nl_data::{self, WirelessState};
+use crate::nl_event::NlMonitor;
+use crate::output::ClickEvent;
 use crate::pulse::Pulse;
 use crate::{Config as MainConfig, ModuleMsg};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -20,6 +24,7 @@ const MAX_ESSID_LEN: usize = 10;
 const INTERFACE: &str = "wlan0";
 const TEXT: &str = "wle";
 const DISCONNECTED_TEXT: &str = ".wl";
+const EVENT_DRIVEN: bool = false;
 
 #[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 enum Display {
@@ -33,10 +38,19 @@ pub struct Config {
     tick: Option<u32>,
     display: Option<Display>,
     max_essid_len: Option<usize>,
+    /// A regex pattern matched against `/sys/class/net/*` entries that
+    /// expose a `wireless`/`phy80211` subdirectory; the first match (sorted
+    /// by name) is used. A plain name like `"wlan0"` matches itself, so
+    /// existing configs keep working.
     interface: Option<String>,
     placeholder: Option<String>,
     text: Option<String>,
     disconnected_text: Option<String>,
+    /// When `true`, block on netlink link/nl80211 events instead of
+    /// polling every `tick`. `tick` still fires as a fallback so the
+    /// signal strength of an already-associated link gets refreshed, since
+    /// RSSI changes aren't always pushed by the kernel.
+    event_driven: Option<bool>,
 }
 
 #[derive(Debug)]
@@ -47,6 +61,15 @@ pub struct InternalConfig<'a> {
     tick: Duration,
     text: &'a str,
     disconnected_text: &'a str,
+    event_driven: bool,
+}
+
+impl<'a> InternalConfig<'a> {
+    /// `interface` is a regex pattern (a plain name like `wlan0` matches
+    /// itself), compiled once here rather than on every tick.
+    fn interface_pattern(&self) -> Result<Regex, Error> {
+        Ok(Regex::new(self.interface)?)
+    }
 }
 
 impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
@@ -57,6 +80,7 @@ impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
         let mut interface = INTERFACE;
         let mut text = TEXT;
         let mut disconnected_text = DISCONNECTED_TEXT;
+        let mut event_driven = EVENT_DRIVEN;
         if let Some(c) = &config.wireless {
             if let Some(t) = c.tick {
                 tick = Duration::from_millis(t as u64)
@@ -76,6 +100,9 @@ impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
             if let Some(v) = &c.disconnected_text {
                 disconnected_text = v;
             }
+            if let Some(e) = c.event_driven {
+                event_driven = e;
+            }
         };
         InternalConfig {
             display,
@@ -84,6 +111,7 @@ impl<'a> From<&'a MainConfig> for InternalConfig<'a> {
             tick,
             text,
             disconnected_text,
+            event_driven,
         }
     }
 }
@@ -109,7 +137,7 @@ impl<'a> Wireless<'a> {
     }
 }
 
-impl<'a> BaruMod for Wireless<'a> {
+impl<'a> Bar for Wireless<'a> {
     fn run_fn(&self) -> RunPtr {
         run
     }
@@ -128,10 +156,20 @@ pub fn run(
     main_config: MainConfig,
     _: Arc<Mutex<Pulse>>,
     tx: Sender<ModuleMsg>,
+    _: Receiver<ClickEvent>,
 ) -> Result<(), Error> {
     let config = InternalConfig::from(&main_config);
+    let pattern = config.interface_pattern()?;
+    let monitor = if config.event_driven {
+        Some(NlMonitor::new()?)
+    } else {
+        None
+    };
     loop {
-        let state = nl_data::wireless_data(&config.interface);
+        let state = match discovery::discover_wireless_interface(&pattern) {
+            Some(interface) => nl_data::wireless_data(&interface),
+            None => WirelessState::Disconnected,
+        };
         let text;
         let mut essid = "".to_owned();
         let mut signal = None;
@@ -151,16 +189,24 @@ pub fn run(
             text = config.disconnected_text;
         }
         match config.display {
-            Display::TextOnly => tx.send(ModuleMsg(key, text.to_string()))?,
-            Display::Essid => tx.send(ModuleMsg(key, format!("{}{}", essid, text)))?,
+            Display::TextOnly => tx.send(ModuleMsg(key, text.to_string(), None))?,
+            Display::Essid => tx.send(ModuleMsg(key, format!("{}{}", essid, text), None))?,
             Display::Signal => {
                 if let Some(s) = signal {
-                    tx.send(ModuleMsg(key, format!("{:3}%{}", s, text)))?;
+                    tx.send(ModuleMsg(key, format!("{:3}%{}", s, text), None))?;
                 } else {
-                    tx.send(ModuleMsg(key, format!("    {}", text)))?;
+                    tx.send(ModuleMsg(key, format!("    {}", text), None))?;
                 }
             }
         }
-        thread::sleep(config.tick);
+        match &monitor {
+            // Block until a link/nl80211 event arrives; the tick still acts
+            // as a fallback timeout so an associated link's signal gets
+            // refreshed periodically.
+            Some(monitor) => {
+                monitor.wait(Some(config.tick))?;
+            }
+            None => thread::sleep(config.tick),
+        }
     }
 }